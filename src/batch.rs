@@ -0,0 +1,100 @@
+//! Run several closures on the worker thread behind a single channel
+//! round-trip, instead of paying one `flume` send + `oneshot` await per query.
+
+use crate::{errors, BoxedError, BoxedQuery, Connection};
+
+/// How [`Connection::batch`] behaves when one of its closures errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchMode {
+    /// Abort the remaining closures as soon as one errors.
+    StopOnError,
+    /// Run every closure regardless of earlier failures.
+    CollectAll,
+}
+
+impl Connection {
+    /// Run `queries` sequentially on the worker thread and return their
+    /// results, in order, as a single awaitable.
+    ///
+    /// In [`BatchMode::StopOnError`] mode the returned `Vec` ends at the
+    /// first error (tagged with its index via [`errors::ErrorKind::Batch`])
+    /// and the remaining closures never run. In [`BatchMode::CollectAll`]
+    /// mode every closure runs and the `Vec` has one entry per query.
+    pub async fn batch<T: Send + Sync + 'static>(
+        &mut self,
+        mode: BatchMode,
+        queries: Vec<BoxedQuery<'static, T, BoxedError<'static>>>,
+    ) -> Result<Vec<Result<T, errors::Error>>, errors::Error> {
+        self.run(move |conn| {
+            let mut results = Vec::with_capacity(queries.len());
+            for (index, query) in queries.into_iter().enumerate() {
+                match query(conn) {
+                    Ok(value) => results.push(Ok(value)),
+                    Err(source) => {
+                        results.push(Err(errors::ErrorKind::Batch { index, source }.into()));
+                        if mode == BatchMode::StopOnError {
+                            break;
+                        }
+                    }
+                }
+            }
+            Ok(results)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exec(sql: &'static str) -> BoxedQuery<'static, (), BoxedError<'static>> {
+        Box::new(move |conn| conn.execute_batch(sql).map_err(Into::into))
+    }
+
+    fn failing() -> BoxedQuery<'static, (), BoxedError<'static>> {
+        Box::new(|conn| {
+            conn.execute_batch("this is not valid sql")
+                .map_err(Into::into)
+        })
+    }
+
+    #[tokio::test]
+    async fn collect_all_runs_every_closure() {
+        let mut conn = Connection::open_in_memory().expect("open");
+        let results = conn
+            .batch(
+                BatchMode::CollectAll,
+                vec![
+                    exec("CREATE TABLE foo (id INTEGER PRIMARY KEY)"),
+                    failing(),
+                    exec("INSERT INTO foo DEFAULT VALUES"),
+                ],
+            )
+            .await
+            .expect("batch");
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+        assert!(results[2].is_ok());
+    }
+
+    #[tokio::test]
+    async fn stop_on_error_aborts_remaining_closures() {
+        let mut conn = Connection::open_in_memory().expect("open");
+        let results = conn
+            .batch(
+                BatchMode::StopOnError,
+                vec![
+                    exec("CREATE TABLE foo (id INTEGER PRIMARY KEY)"),
+                    failing(),
+                    exec("INSERT INTO foo DEFAULT VALUES"),
+                ],
+            )
+            .await
+            .expect("batch");
+        assert_eq!(results.len(), 2, "third closure should never have run");
+        assert!(results[0].is_ok());
+        assert!(results[1].is_err());
+    }
+}
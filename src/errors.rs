@@ -21,6 +21,12 @@ pub enum ErrorKind {
     Other(#[from] BoxedError<'static>),
     #[error("Database Connection Closed")]
     Closed,
+    #[error("batch closure {index} failed")]
+    Batch {
+        index: usize,
+        #[source]
+        source: BoxedError<'static>,
+    },
 }
 
 impl<E> From<E> for Error
@@ -52,6 +58,7 @@ impl ErrorKind {
             Self::FlumeRecv(_) => None,
             Self::Other(e) => e.downcast_ref(),
             Self::Closed => None,
+            Self::Batch { source, .. } => source.downcast_ref(),
         }
     }
 }
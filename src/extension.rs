@@ -0,0 +1,103 @@
+//! Runtime loading of SQLite extensions (e.g. `crsqlite`, `sqlite-vss`),
+//! gated behind the `load_extension` feature.
+
+use crate::{errors, BoxedQuery, Connection, Message};
+use rusqlite::Connection as SqliteConnection;
+use std::path::{Path, PathBuf};
+
+impl Connection {
+    /// Load a SQLite extension into this connection.
+    ///
+    /// Extension loading is enabled only for the duration of the call, all
+    /// on the worker thread, so the raw FFI handle never leaves it and the
+    /// capability isn't left switched on afterwards.
+    pub async fn load_extension(
+        &self,
+        path: impl AsRef<Path> + Send + 'static,
+        entry_point: Option<impl AsRef<str> + Send + 'static>,
+    ) -> Result<(), errors::Error> {
+        self.delegate(move |conn| {
+            // SAFETY: disabled again immediately below, on the same thread.
+            unsafe {
+                conn.load_extension_enable()?;
+                let res = conn.load_extension(path, entry_point.as_ref().map(AsRef::as_ref));
+                conn.load_extension_disable()?;
+                res
+            }
+        })
+        .await
+    }
+
+    /// Open a connection and load `extensions` on the worker thread right
+    /// after, so every query that follows is guaranteed to see them —
+    /// useful for extensions like `crsqlite` that must be initialized
+    /// before use.
+    pub fn open_with_extensions(
+        path: impl AsRef<Path>,
+        extensions: &[(PathBuf, Option<String>)],
+    ) -> Result<Self, errors::Error> {
+        let path = path.as_ref().to_owned();
+        let extensions = extensions.to_vec();
+        Self::open_with(move || {
+            let conn = SqliteConnection::open(path)?;
+            unsafe {
+                conn.load_extension_enable()?;
+                for (path, entry_point) in &extensions {
+                    conn.load_extension(path, entry_point.as_deref())?;
+                }
+                conn.load_extension_disable()?;
+            }
+            Ok(conn)
+        })
+    }
+
+    /// Register a closure that runs once on the worker thread right before
+    /// the connection is closed, e.g. to call `SELECT crsql_finalize()`
+    /// before a `crsqlite`-backed connection drops.
+    pub fn on_close(
+        &self,
+        f: impl FnOnce(&SqliteConnection) -> Result<(), errors::Error> + Send + 'static,
+    ) -> Result<(), errors::Error> {
+        let f: BoxedQuery<'static, (), errors::Error> = Box::new(f);
+        self.channel
+            .send(Message::SetFinalizer(f))
+            .map_err(|_| errors::ErrorKind::Closed.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn load_extension_reports_missing_file_and_disables_cleanly() {
+        let conn = Connection::open_in_memory().expect("open");
+        let err = conn
+            .load_extension("/nonexistent/path/to/ext.so", None::<&str>)
+            .await;
+        assert!(err.is_err());
+
+        // `load_extension_disable` runs unconditionally after the call above,
+        // so the connection should still be perfectly usable afterwards.
+        conn.delegate(|conn| conn.execute_batch("SELECT 1"))
+            .await
+            .expect("connection still usable after a failed load_extension");
+    }
+
+    #[tokio::test]
+    async fn on_close_finalizer_runs_before_the_worker_closes() {
+        let conn = Connection::open_in_memory().expect("open");
+        let (tx, rx) = flume::bounded(1);
+        conn.on_close(move |_conn| {
+            tx.send(()).ok();
+            Ok(())
+        })
+        .expect("register finalizer");
+
+        drop(conn);
+
+        rx.recv_timeout(Duration::from_secs(1))
+            .expect("finalizer should have run before the connection closed");
+    }
+}
@@ -35,8 +35,23 @@
 //! }
 //! ```
 
+#[cfg(feature = "backup")]
+mod backup;
+#[cfg(feature = "backup")]
+pub use backup::ProgressSender;
+mod batch;
+pub use batch::BatchMode;
 mod delegate;
 mod errors;
+#[cfg(feature = "load_extension")]
+mod extension;
+#[cfg(feature = "pool")]
+pub mod pool;
+#[cfg(feature = "trace")]
+mod trace;
+mod transaction;
+#[cfg(feature = "trace")]
+pub use trace::TraceLevel;
 /// Re-export of the whole rusqlite crate
 pub mod rusqlite {
     pub use rusqlite::Connection as SqliteConnection;
@@ -78,6 +93,8 @@ impl Drop for Connection {
 pub(crate) enum Message {
     Query(BoxedQuery<'static, (), errors::Error>),
     QueryMut(BoxedQueryMut<'static, (), errors::Error>),
+    #[cfg(feature = "load_extension")]
+    SetFinalizer(BoxedQuery<'static, (), errors::Error>),
     Close,
 }
 
@@ -113,15 +130,25 @@ impl Connection {
         let path = path.as_ref().to_owned();
         let handle = std::thread::spawn(move || -> Result<(), errors::Error> {
             let mut conn = rusqlite::Connection::open(path)?;
+            #[cfg(feature = "load_extension")]
+            let mut finalizer: Option<BoxedQuery<'static, (), errors::Error>> = None;
             for msg in rx.into_iter() {
                 match msg {
-                    Message::Close => break,
+                    Message::Close => {
+                        #[cfg(feature = "load_extension")]
+                        if let Some(finalizer) = finalizer.take() {
+                            finalizer(&conn)?;
+                        }
+                        break;
+                    }
                     Message::Query(wrapped_query) => {
                         wrapped_query(&conn)?;
                     }
                     Message::QueryMut(wrapped_query) => {
                         wrapped_query(&mut conn)?;
                     }
+                    #[cfg(feature = "load_extension")]
+                    Message::SetFinalizer(f) => finalizer = Some(f),
                 }
             }
             Ok(())
@@ -139,15 +166,25 @@ impl Connection {
         let (tx, rx) = flume::unbounded::<Message>();
         let handle = std::thread::spawn(move || -> Result<(), errors::Error> {
             let mut conn = f()?;
+            #[cfg(feature = "load_extension")]
+            let mut finalizer: Option<BoxedQuery<'static, (), errors::Error>> = None;
             for msg in rx.into_iter() {
                 match msg {
-                    Message::Close => break,
+                    Message::Close => {
+                        #[cfg(feature = "load_extension")]
+                        if let Some(finalizer) = finalizer.take() {
+                            finalizer(&conn)?;
+                        }
+                        break;
+                    }
                     Message::Query(wrapped_query) => {
                         wrapped_query(&mut conn)?;
                     }
                     Message::QueryMut(wrapped_query) => {
                         wrapped_query(&mut conn)?;
                     }
+                    #[cfg(feature = "load_extension")]
+                    Message::SetFinalizer(f) => finalizer = Some(f),
                 }
             }
             Ok(())
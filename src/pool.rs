@@ -0,0 +1,96 @@
+//! An async connection pool over many worker-thread-backed [`Connection`]s,
+//! built on [`bb8`]'s [`bb8::ManageConnection`].
+//!
+//! A single [`Connection`] serializes every query through one worker thread.
+//! [`Pool`] fans work out across `N` of them instead, which is the shape you
+//! want once the database is opened in WAL mode and callers can genuinely
+//! run in parallel.
+//!
+//! ```rust,no_run
+//! # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+//! use rusqlite_async::pool::{ConnectionManager, Pool};
+//!
+//! let manager = ConnectionManager::open("my.db");
+//! let pool = Pool::builder().max_size(8).build(manager).await?;
+//! let conn = pool.get().await?;
+//! conn.delegate(|conn| conn.execute_batch("SELECT 1")).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{errors, Connection};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Knows how to open fresh [`Connection`]s for a [`bb8::Pool`] and validate
+/// them on checkout.
+#[derive(Clone)]
+pub struct ConnectionManager {
+    open: Arc<dyn Fn() -> Result<Connection, errors::Error> + Send + Sync>,
+}
+
+impl ConnectionManager {
+    /// Create a manager that opens new connections to the database file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Self {
+        let path: PathBuf = path.as_ref().to_owned();
+        Self {
+            open: Arc::new(move || Connection::open(&path).map_err(Into::into)),
+        }
+    }
+
+    /// Create a manager that opens new connections via a custom constructor,
+    /// mirroring [`Connection::open_with`].
+    pub fn open_with<F>(f: F) -> Self
+    where
+        F: Fn() -> Result<crate::rusqlite::SqliteConnection, errors::Error> + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+        Self {
+            open: Arc::new(move || {
+                let f = Arc::clone(&f);
+                Connection::open_with(move || f())
+            }),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl bb8::ManageConnection for ConnectionManager {
+    type Connection = Connection;
+    type Error = errors::Error;
+
+    async fn connect(&self) -> Result<Self::Connection, Self::Error> {
+        (self.open)()
+    }
+
+    async fn is_valid(&self, conn: &mut Self::Connection) -> Result<(), Self::Error> {
+        conn.delegate(|conn| conn.execute_batch("SELECT 1")).await
+    }
+
+    fn has_broken(&self, _conn: &mut Self::Connection) -> bool {
+        false
+    }
+}
+
+/// An async pool of [`Connection`]s. See the [module docs](self) for an example.
+pub type Pool = bb8::Pool<ConnectionManager>;
+
+/// A pooled [`Connection`] checked out of a [`Pool`]; derefs to [`Connection`]
+/// so `delegate`/`delegate_mut`/`run` work directly on the guard.
+pub type PooledConnection<'a> = bb8::PooledConnection<'a, ConnectionManager>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn get_checks_out_a_working_connection() {
+        let manager = ConnectionManager::open_with(|| Ok(crate::rusqlite::SqliteConnection::open_in_memory()?));
+        let pool = Pool::builder().max_size(2).build(manager).await.expect("build pool");
+
+        let conn = pool.get().await.expect("checkout");
+        conn.delegate(|conn| conn.execute_batch("CREATE TABLE foo (id INTEGER PRIMARY KEY)"))
+            .await
+            .expect("query through guard");
+    }
+}
@@ -0,0 +1,120 @@
+//! Forwards SQLite's statement-trace and profile callbacks into `tracing`,
+//! gated behind the `trace` feature.
+//!
+//! `rusqlite::Connection::trace`/`profile` take a plain, non-capturing
+//! `fn(&str)`/`fn(&str, Duration)` pointer (not a closure), because they're
+//! backed by the `sqlite3_trace`/`sqlite3_profile` APIs which carry no user
+//! data pointer. Since SQLite invokes them synchronously from whichever
+//! thread ran the statement — always this connection's worker thread — a
+//! thread-local holds the configuration the fn pointer reads.
+
+use crate::{errors, Connection};
+use std::cell::RefCell;
+use std::time::Duration;
+
+/// Verbosity used by [`Connection::trace_enable`] for the emitted `tracing` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceLevel {
+    Trace,
+    Debug,
+    Info,
+}
+
+thread_local! {
+    static TRACE_LEVEL: RefCell<TraceLevel> = const { RefCell::new(TraceLevel::Debug) };
+    static PROFILE_SINK: RefCell<Option<Box<dyn Fn(&str, Duration) + Send>>> = const { RefCell::new(None) };
+}
+
+fn trace_callback(sql: &str) {
+    match TRACE_LEVEL.with(|level| *level.borrow()) {
+        TraceLevel::Trace => tracing::trace!(sql, "sqlite trace"),
+        TraceLevel::Debug => tracing::debug!(sql, "sqlite trace"),
+        TraceLevel::Info => tracing::info!(sql, "sqlite trace"),
+    }
+}
+
+fn profile_callback(sql: &str, duration: Duration) {
+    PROFILE_SINK.with(|sink| match &*sink.borrow() {
+        Some(sink) => sink(sql, duration),
+        None => tracing::debug!(sql, ?duration, "sqlite profile"),
+    });
+}
+
+impl Connection {
+    /// Emit a `tracing` event with the expanded SQL text for every statement
+    /// run on this connection.
+    pub async fn trace_enable(&mut self, level: TraceLevel) -> Result<(), errors::Error> {
+        self.delegate_mut(move |conn| {
+            TRACE_LEVEL.with(|l| *l.borrow_mut() = level);
+            conn.trace(Some(trace_callback));
+            Ok(())
+        })
+        .await
+    }
+
+    /// Remove a previously installed trace callback.
+    pub async fn trace_disable(&mut self) -> Result<(), errors::Error> {
+        self.delegate_mut(|conn| {
+            conn.trace(None);
+            Ok(())
+        })
+        .await
+    }
+
+    /// Emit every statement's SQL and its execution [`Duration`] once it
+    /// finishes, either as a `tracing::debug!` event or, if `sink` is given,
+    /// routed there instead (e.g. into a metrics recorder).
+    pub async fn profile_enable(
+        &mut self,
+        sink: Option<Box<dyn Fn(&str, Duration) + Send + 'static>>,
+    ) -> Result<(), errors::Error> {
+        self.delegate_mut(move |conn| {
+            PROFILE_SINK.with(|s| *s.borrow_mut() = sink);
+            conn.profile(Some(profile_callback));
+            Ok(())
+        })
+        .await
+    }
+
+    /// Remove a previously installed profile callback.
+    pub async fn profile_disable(&mut self) -> Result<(), errors::Error> {
+        self.delegate_mut(|conn| {
+            conn.profile(None);
+            Ok(())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn trace_enable_and_disable_around_query() {
+        let mut conn = Connection::open_in_memory().expect("open");
+        conn.trace_enable(TraceLevel::Info).await.expect("enable trace");
+        conn.delegate(|conn| conn.execute_batch("SELECT 1"))
+            .await
+            .expect("query");
+        conn.trace_disable().await.expect("disable trace");
+    }
+
+    #[tokio::test]
+    async fn profile_enable_reports_to_sink() {
+        let mut conn = Connection::open_in_memory().expect("open");
+        let seen: Arc<Mutex<Vec<String>>> = Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = Arc::clone(&seen);
+        conn.profile_enable(Some(Box::new(move |sql: &str, _duration: Duration| {
+            sink_seen.lock().unwrap().push(sql.to_owned());
+        })))
+        .await
+        .expect("enable profile");
+        conn.delegate(|conn| conn.execute_batch("SELECT 1"))
+            .await
+            .expect("query");
+        conn.profile_disable().await.expect("disable profile");
+        assert!(!seen.lock().unwrap().is_empty(), "profile callback never fired");
+    }
+}
@@ -0,0 +1,131 @@
+//! Async transaction and savepoint support.
+//!
+//! Because the [`SqliteConnection`] is pinned to the worker thread, callers
+//! can't hold a [`rusqlite::Transaction`] across an `.await`. These methods
+//! run the whole atomic unit on the worker thread instead, so the caller
+//! gets a single awaitable that either fully commits or fully rolls back.
+
+use crate::{errors, Connection};
+use rusqlite::{Connection as SqliteConnection, TransactionBehavior};
+
+impl Connection {
+    /// Run `f` inside a transaction, committing on `Ok` and rolling back on
+    /// `Err` (or on panic, via [`rusqlite::Transaction`]'s `Drop` impl).
+    pub async fn transaction<T: Send + Sync + 'static>(
+        &mut self,
+        behavior: TransactionBehavior,
+        f: impl FnOnce(&rusqlite::Transaction<'_>) -> Result<T, rusqlite::Error> + Send + 'static,
+    ) -> Result<T, errors::Error> {
+        self.delegate_mut(move |conn: &mut SqliteConnection| {
+            let txn = conn.transaction_with_behavior(behavior)?;
+            match f(&txn) {
+                Ok(value) => {
+                    txn.commit()?;
+                    Ok(value)
+                }
+                Err(e) => {
+                    txn.rollback()?;
+                    Err(e)
+                }
+            }
+        })
+        .await
+    }
+
+    /// Run `f` inside a named savepoint, nestable within an existing
+    /// transaction, committing on `Ok` and rolling back on `Err` (or panic).
+    ///
+    /// `name` takes anything convertible to `String` so nested scopes can use
+    /// loop- or depth-generated names (e.g. `format!("sp_{depth}")`) and
+    /// aren't forced into `'static` string literals.
+    pub async fn savepoint<T: Send + Sync + 'static>(
+        &mut self,
+        name: impl Into<String> + Send + 'static,
+        f: impl FnOnce(&rusqlite::Savepoint<'_>) -> Result<T, rusqlite::Error> + Send + 'static,
+    ) -> Result<T, errors::Error> {
+        let name = name.into();
+        self.delegate_mut(move |conn: &mut SqliteConnection| {
+            let mut sp = conn.savepoint_with_name(&name)?;
+            match f(&sp) {
+                Ok(value) => {
+                    sp.commit()?;
+                    Ok(value)
+                }
+                Err(e) => {
+                    sp.rollback()?;
+                    Err(e)
+                }
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn seeded() -> Connection {
+        let mut conn = Connection::open_in_memory().expect("open");
+        conn.delegate(|conn| conn.execute_batch("CREATE TABLE foo (id INTEGER PRIMARY KEY, val TEXT)"))
+            .await
+            .expect("create table");
+        conn
+    }
+
+    async fn count(conn: &Connection) -> i64 {
+        conn.delegate(|conn| conn.query_row("SELECT COUNT(*) FROM foo", [], |row| row.get(0)))
+            .await
+            .expect("count")
+    }
+
+    #[tokio::test]
+    async fn transaction_commits_on_ok() {
+        let mut conn = seeded().await;
+        conn.transaction(TransactionBehavior::Immediate, |txn| {
+            txn.execute("INSERT INTO foo (val) VALUES ('a')", [])?;
+            Ok(())
+        })
+        .await
+        .expect("transaction");
+        assert_eq!(count(&conn).await, 1);
+    }
+
+    #[tokio::test]
+    async fn transaction_rolls_back_on_err() {
+        let mut conn = seeded().await;
+        let res = conn
+            .transaction(TransactionBehavior::Immediate, |txn| {
+                txn.execute("INSERT INTO foo (val) VALUES ('a')", [])?;
+                Err(rusqlite::Error::ExecuteReturnedResults)
+            })
+            .await;
+        assert!(res.is_err());
+        assert_eq!(count(&conn).await, 0);
+    }
+
+    #[tokio::test]
+    async fn savepoint_commits_on_ok() {
+        let mut conn = seeded().await;
+        conn.savepoint("sp_test", |sp| {
+            sp.execute("INSERT INTO foo (val) VALUES ('a')", [])?;
+            Ok(())
+        })
+        .await
+        .expect("savepoint");
+        assert_eq!(count(&conn).await, 1);
+    }
+
+    #[tokio::test]
+    async fn savepoint_rolls_back_on_err() {
+        let mut conn = seeded().await;
+        let res = conn
+            .savepoint(format!("sp_{}", 0), |sp| {
+                sp.execute("INSERT INTO foo (val) VALUES ('a')", [])?;
+                Err(rusqlite::Error::ExecuteReturnedResults)
+            })
+            .await;
+        assert!(res.is_err());
+        assert_eq!(count(&conn).await, 0);
+    }
+}